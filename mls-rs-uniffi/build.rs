@@ -3,10 +3,23 @@
 
 fn main() {
     println!("cargo:warning=MLS-RS UniFFI build script running");
-    
+
     // Just emit the rerun directives
     println!("cargo:rerun-if-changed=src/");
     println!("cargo:rerun-if-changed=Cargo.toml");
-    
-    println!("cargo:warning=Build script completed - use ./bindings/build-xcframework.sh to generate Swift bindings");
+
+    // Cargo runs build.rs *before* the crate's cdylib is compiled, so there is
+    // no compiled library yet for UniFFI's library mode to read here - a
+    // cargo feature gating this script can't make bindings generation happen
+    // automatically. Generate them as a separate step once `cargo build` has
+    // finished, via the `uniffi-bindgen` binary's `--languages` library-mode
+    // path (see uniffi-bindgen/src/main.rs), e.g.:
+    //
+    //   cargo run --bin uniffi-bindgen -- --languages swift \
+    //       --lib-path target/release/libmls_rs_uniffi.so --out-dir <out-dir>
+    println!(
+        "cargo:warning=Build script completed - use ./bindings/build-xcframework.sh, or run \
+         `uniffi-bindgen --languages ... --lib-path ... --out-dir ...` after this build finishes, \
+         to generate bindings"
+    );
 }
@@ -0,0 +1,60 @@
+//! Known Apple target triples selectable via `--targets`, covering device,
+//! simulator, and watchOS slices for the xcframework.
+
+/// `(alias, rustc target triple)`, in the order `--targets all` expands to.
+const KNOWN_TARGETS: &[(&str, &str)] = &[
+    ("ios", "aarch64-apple-ios"),
+    ("ios-sim", "aarch64-apple-ios-sim"),
+    ("ios-sim-x86_64", "x86_64-apple-ios"),
+    ("macos", "aarch64-apple-darwin"),
+    ("macos-x86_64", "x86_64-apple-darwin"),
+    ("watchos", "aarch64-apple-watchos"),
+    ("watchos-sim", "aarch64-apple-watchos-sim"),
+    ("watchos-sim-x86_64", "x86_64-apple-watchos-sim"),
+];
+
+/// Resolves a comma-separated `--targets` value (aliases from
+/// [`KNOWN_TARGETS`], or `all`) into the rustc target triples to build.
+pub fn resolve(spec: &str) -> Vec<String> {
+    if spec == "all" {
+        return KNOWN_TARGETS
+            .iter()
+            .map(|(_, triple)| triple.to_string())
+            .collect();
+    }
+
+    spec.split(',')
+        .map(|alias| {
+            let alias = alias.trim();
+            KNOWN_TARGETS
+                .iter()
+                .find(|(known_alias, _)| *known_alias == alias)
+                .map(|(_, triple)| triple.to_string())
+                .unwrap_or_else(|| panic!("unknown --targets entry `{alias}`"))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolve_expands_aliases_to_triples() {
+        assert_eq!(
+            resolve("ios,watchos-sim"),
+            vec!["aarch64-apple-ios", "aarch64-apple-watchos-sim"]
+        );
+    }
+
+    #[test]
+    fn resolve_all_returns_every_known_target() {
+        assert_eq!(resolve("all").len(), KNOWN_TARGETS.len());
+    }
+
+    #[test]
+    #[should_panic(expected = "unknown --targets entry")]
+    fn resolve_rejects_unknown_alias() {
+        resolve("ios,bogus");
+    }
+}
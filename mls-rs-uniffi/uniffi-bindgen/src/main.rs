@@ -1,9 +1,128 @@
 use std::env;
+use std::path::PathBuf;
+
+use camino::Utf8PathBuf;
+use uniffi_bindgen::bindings::TargetLanguage;
+use uniffi_bindgen::library_mode::generate_bindings;
+use uniffi_bindgen::BindingGeneratorDefault;
+
+mod apple_targets;
+mod swift_mode;
+mod xcframework;
+
+use swift_mode::SwiftMode;
+use xcframework::{TargetArtifact, XcframeworkOptions};
+
+/// Parses a repeated `--xcframework-target` argument. Either a fully
+/// resolved `<triple>=<library>=<headers>` for an artifact that was already
+/// built elsewhere, or a bare `<triple>` that this binary should build
+/// itself via cargo before assembly.
+enum XcframeworkTargetSpec {
+    Prebuilt(TargetArtifact),
+    ToBuild(String),
+}
+
+fn parse_xcframework_target(spec: &str) -> XcframeworkTargetSpec {
+    if !spec.contains('=') {
+        return XcframeworkTargetSpec::ToBuild(spec.to_string());
+    }
+
+    let mut parts = spec.splitn(3, '=');
+    let triple = parts.next().expect("--xcframework-target needs a triple");
+    let library = parts
+        .next()
+        .expect("--xcframework-target needs a library path");
+    let headers = parts
+        .next()
+        .expect("--xcframework-target needs a headers path");
+
+    XcframeworkTargetSpec::Prebuilt(TargetArtifact {
+        triple: triple.to_string(),
+        library: PathBuf::from(library),
+        headers: PathBuf::from(headers),
+    })
+}
+
+/// Parses a comma-separated `--languages` value (e.g. `swift,kotlin,python`)
+/// into the `TargetLanguage`s UniFFI knows how to generate for. Unknown
+/// entries are reported and skipped rather than aborting the whole run, but
+/// a `--languages` value that resolves to nothing is an error rather than a
+/// silent no-op.
+fn parse_languages(spec: &str) -> Vec<TargetLanguage> {
+    let languages: Vec<TargetLanguage> = spec
+        .split(',')
+        .filter_map(|lang| match lang.trim() {
+            "swift" => Some(TargetLanguage::Swift),
+            "kotlin" => Some(TargetLanguage::Kotlin),
+            "python" => Some(TargetLanguage::Python),
+            other => {
+                eprintln!("warning: ignoring unknown --languages entry `{other}`");
+                None
+            }
+        })
+        .collect();
+
+    assert!(
+        !languages.is_empty(),
+        "--languages `{spec}` did not resolve to any supported target language"
+    );
+
+    languages
+}
+
+/// Drives UniFFI's library-mode generator once per requested target language
+/// so a single invocation can emit Swift bindings for iOS alongside
+/// Kotlin/JNA bindings for Android. `swift_mode`, when set, is applied only
+/// to the Swift pass.
+fn generate_multi_language_bindings(
+    lib_path: &Utf8PathBuf,
+    out_dir: &Utf8PathBuf,
+    languages: Vec<TargetLanguage>,
+    swift_mode: Option<SwiftMode>,
+) {
+    for target_language in languages {
+        let generator = BindingGeneratorDefault {
+            target_languages: vec![target_language],
+            try_format_code: false,
+        };
+
+        let metadata_config = match (target_language, swift_mode) {
+            (TargetLanguage::Swift, Some(mode)) => Some(swift_mode::write_config(mode, out_dir)),
+            _ => None,
+        };
+
+        generate_bindings(
+            lib_path,
+            None,
+            &generator,
+            metadata_config.as_deref(),
+            out_dir,
+            false,
+        )
+        .unwrap_or_else(|e| panic!("failed to generate {target_language:?} bindings: {e}"));
+    }
+}
 
 fn main() {
     // Check for iOS-specific command line arguments
     let args: Vec<String> = env::args().collect();
-    
+
+    let mut languages: Option<Vec<TargetLanguage>> = None;
+    let mut lib_path: Option<Utf8PathBuf> = None;
+    let mut out_dir: Option<Utf8PathBuf> = None;
+
+    let mut build_xcframework = false;
+    let mut xcframework_target_specs: Vec<XcframeworkTargetSpec> = Vec::new();
+    let mut module_map: Option<PathBuf> = None;
+    let mut release_tag: Option<String> = None;
+    let mut package_swift: Option<PathBuf> = None;
+    let mut xcframework_out_dir: Option<PathBuf> = None;
+    let mut crate_name: Option<String> = None;
+    let mut headers_dir: Option<PathBuf> = None;
+    let mut clean_env = false;
+    let mut swift_mode: Option<SwiftMode> = None;
+    let mut ios_deployment_target: Option<String> = None;
+
     // Look for iOS-specific flags and set environment variables
     for i in 0..args.len() {
         match args[i].as_str() {
@@ -20,12 +139,183 @@ fn main() {
             "--ios-deployment-target" => {
                 if i + 1 < args.len() {
                     env::set_var("UNIFFI_IOS_DEPLOYMENT_TARGET", &args[i + 1]);
+                    ios_deployment_target = Some(args[i + 1].clone());
+                }
+            }
+            "--languages" => {
+                if i + 1 < args.len() {
+                    languages = Some(parse_languages(&args[i + 1]));
+                }
+            }
+            "--lib-path" => {
+                if i + 1 < args.len() {
+                    lib_path = Some(Utf8PathBuf::from(&args[i + 1]));
+                }
+            }
+            "--out-dir" => {
+                if i + 1 < args.len() {
+                    out_dir = Some(Utf8PathBuf::from(&args[i + 1]));
+                }
+            }
+            "--build-xcframework" => {
+                build_xcframework = true;
+            }
+            "--xcframework-target" => {
+                if i + 1 < args.len() {
+                    xcframework_target_specs.push(parse_xcframework_target(&args[i + 1]));
+                }
+            }
+            "--crate-name" => {
+                if i + 1 < args.len() {
+                    crate_name = Some(args[i + 1].clone());
+                }
+            }
+            "--headers-dir" => {
+                if i + 1 < args.len() {
+                    headers_dir = Some(PathBuf::from(&args[i + 1]));
+                }
+            }
+            "--clean-env" => {
+                clean_env = true;
+            }
+            "--targets" => {
+                if i + 1 < args.len() {
+                    for triple in apple_targets::resolve(&args[i + 1]) {
+                        xcframework_target_specs.push(XcframeworkTargetSpec::ToBuild(triple));
+                    }
+                }
+            }
+            "--swift-mode" => {
+                if i + 1 < args.len() {
+                    swift_mode = Some(SwiftMode::parse(&args[i + 1]));
+                }
+            }
+            "--module-map" => {
+                if i + 1 < args.len() {
+                    module_map = Some(PathBuf::from(&args[i + 1]));
+                }
+            }
+            "--release-tag" => {
+                if i + 1 < args.len() {
+                    release_tag = Some(args[i + 1].clone());
+                }
+            }
+            "--package-swift" => {
+                if i + 1 < args.len() {
+                    package_swift = Some(PathBuf::from(&args[i + 1]));
+                }
+            }
+            "--xcframework-out-dir" => {
+                if i + 1 < args.len() {
+                    xcframework_out_dir = Some(PathBuf::from(&args[i + 1]));
                 }
             }
             _ => {}
         }
     }
-    
+
+    // `--build-xcframework` replaces the external build-xcframework.sh script:
+    // bundle the per-target artifacts, zip the result, and patch the checksum
+    // into Package.swift.
+    if build_xcframework {
+        let framework_name = env::var("UNIFFI_FRAMEWORK_NAME")
+            .expect("--build-xcframework requires --framework-name");
+        let module_map = module_map.expect("--build-xcframework requires --module-map");
+        let release_tag = release_tag.expect("--build-xcframework requires --release-tag");
+        let package_swift =
+            package_swift.expect("--build-xcframework requires --package-swift");
+        let output_dir = xcframework_out_dir.unwrap_or_else(|| PathBuf::from("."));
+
+        assert!(
+            !xcframework_target_specs.is_empty(),
+            "--build-xcframework requires at least one --xcframework-target or --targets entry"
+        );
+
+        // Targets passed as a bare triple (no `=library=headers`) haven't
+        // been built yet; build them here, spawning cargo with an env scrub
+        // when `--clean-env` is set so a desktop toolchain/env can't leak
+        // into the cross-compiled iOS output.
+        let xcframework_targets: Vec<TargetArtifact> = xcframework_target_specs
+            .into_iter()
+            .map(|spec| match spec {
+                XcframeworkTargetSpec::Prebuilt(artifact) => artifact,
+                XcframeworkTargetSpec::ToBuild(triple) => {
+                    let crate_name = crate_name
+                        .as_deref()
+                        .expect("building a target requires --crate-name");
+                    let headers = headers_dir
+                        .clone()
+                        .expect("building a target requires --headers-dir");
+
+                    let library = xcframework::build_target_with_cargo(
+                        &triple,
+                        crate_name,
+                        clean_env,
+                        ios_deployment_target.as_deref(),
+                    )
+                    .unwrap_or_else(|e| panic!("failed to build {triple} with cargo: {e}"));
+
+                    TargetArtifact {
+                        triple,
+                        library,
+                        headers,
+                    }
+                }
+            })
+            .collect();
+
+        let opts = XcframeworkOptions {
+            framework_name: &framework_name,
+            artifacts: &xcframework_targets,
+            module_map: &module_map,
+            output_dir: &output_dir,
+            release_tag: &release_tag,
+            package_swift: &package_swift,
+        };
+
+        let zip_path = xcframework::build(&opts).expect("failed to build xcframework");
+        println!("built {}", zip_path.display());
+        return;
+    }
+
+    // When `--languages` is present, drive library-mode generation directly so
+    // we can target more than the Swift-only default entry point below.
+    if let Some(languages) = languages {
+        let lib_path = lib_path.expect("--languages requires --lib-path");
+        let out_dir = out_dir.expect("--languages requires --out-dir");
+
+        generate_multi_language_bindings(&lib_path, &out_dir, languages, swift_mode);
+        return;
+    }
+
     // Call the standard uniffi bindgen main function
     uniffi::uniffi_bindgen_main()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_languages_resolves_known_names() {
+        assert_eq!(
+            parse_languages("swift,kotlin,python"),
+            vec![
+                TargetLanguage::Swift,
+                TargetLanguage::Kotlin,
+                TargetLanguage::Python,
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_languages_skips_unknown_entries() {
+        assert_eq!(parse_languages("swift,ruby"), vec![TargetLanguage::Swift]);
+    }
+
+    #[test]
+    #[should_panic(expected = "did not resolve to any supported target language")]
+    fn parse_languages_rejects_a_fully_unknown_list() {
+        parse_languages("swfit");
+    }
+}
@@ -0,0 +1,94 @@
+//! Maps our `--swift-mode` flag onto UniFFI's real Swift bindings config.
+//!
+//! UniFFI's Swift `Config` (`[bindings.swift]` in `uniffi.toml`) has no
+//! `mode` key; it recognizes `cdylib_name`, `module_name`,
+//! `generate_module_map`, etc. The split-vs-unified layout this flag
+//! controls maps onto `generate_module_map`: `false` emits a single Swift
+//! source file, `true` additionally emits the `include/` headers and
+//! `module.modulemap` an xcframework needs.
+
+use std::fs;
+
+use camino::{Utf8Path, Utf8PathBuf};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SwiftMode {
+    /// A single unified Swift source file, for embedding the crate directly
+    /// in an Xcode target.
+    Source,
+    /// Separate `.swift` + `include/` headers + `module.modulemap`, for
+    /// bundling into a prebuilt `.xcframework`.
+    Framework,
+}
+
+impl SwiftMode {
+    pub fn parse(value: &str) -> SwiftMode {
+        match value {
+            "source" => SwiftMode::Source,
+            "framework" => SwiftMode::Framework,
+            other => panic!("invalid --swift-mode `{other}`, expected `source` or `framework`"),
+        }
+    }
+
+    fn generate_module_map(self) -> bool {
+        match self {
+            SwiftMode::Source => false,
+            SwiftMode::Framework => true,
+        }
+    }
+}
+
+/// Writes a `[bindings.swift] generate_module_map = ...` config snippet into
+/// `out_dir` and returns its path, for passing as `generate_bindings`'s
+/// `metadata_config` argument (which takes a `camino::Utf8Path`, matching
+/// the `Utf8PathBuf` used for `lib_path`/`out_dir`).
+pub fn write_config(mode: SwiftMode, out_dir: &Utf8Path) -> Utf8PathBuf {
+    let path = out_dir.join("uniffi-swift-mode.toml");
+    let contents = format!(
+        "[bindings.swift]\ngenerate_module_map = {}\n",
+        mode.generate_module_map()
+    );
+    fs::write(&path, contents).expect("failed to write --swift-mode config");
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_accepts_known_modes() {
+        assert_eq!(SwiftMode::parse("source"), SwiftMode::Source);
+        assert_eq!(SwiftMode::parse("framework"), SwiftMode::Framework);
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid --swift-mode")]
+    fn parse_rejects_unknown_mode() {
+        SwiftMode::parse("bogus");
+    }
+
+    #[test]
+    fn write_config_emits_the_real_generate_module_map_key() {
+        let dir = std::env::temp_dir().join(format!(
+            "uniffi-bindgen-swift-mode-test-{:?}",
+            std::thread::current().id()
+        ));
+        fs::create_dir_all(&dir).unwrap();
+        let out_dir = Utf8PathBuf::from_path_buf(dir.clone()).unwrap();
+
+        let path = write_config(SwiftMode::Source, &out_dir);
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "[bindings.swift]\ngenerate_module_map = false\n"
+        );
+
+        let path = write_config(SwiftMode::Framework, &out_dir);
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "[bindings.swift]\ngenerate_module_map = true\n"
+        );
+
+        fs::remove_dir_all(&dir).ok();
+    }
+}
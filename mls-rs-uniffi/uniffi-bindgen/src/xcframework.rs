@@ -0,0 +1,437 @@
+//! Assembles an `.xcframework` from per-target build artifacts and keeps a
+//! `Package.swift` template's `binaryTarget` checksum in sync with it.
+//!
+//! This replaces the manual steps that used to live in `build-xcframework.sh`:
+//! running `xcodebuild -create-xcframework`, zipping the result, hashing it,
+//! and patching the checksum into the Swift package manifest.
+
+use std::env;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use sha2::{Digest, Sha256};
+
+/// Env vars, beyond `PATH`, that need to survive a `--clean-env` scrub so the
+/// MLS crypto backend (e.g. the `openssl` feature) can still find its native
+/// dependencies when cross-compiling for Apple targets.
+const CRYPTO_BACKEND_ENV_VARS: &[&str] = &[
+    "OPENSSL_DIR",
+    "OPENSSL_LIB_DIR",
+    "OPENSSL_INCLUDE_DIR",
+    "OPENSSL_STATIC",
+    "OPENSSL_NO_VENDOR",
+];
+
+/// Env vars cargo/rustup themselves need to locate the toolchain and
+/// registry (`~/.cargo`, `~/.rustup` by default); scrubbing these out from
+/// under `--clean-env` makes cargo fail before it ever reaches the linker.
+const CARGO_TOOLCHAIN_ENV_VARS: &[&str] = &["HOME", "CARGO_HOME", "RUSTUP_HOME"];
+
+/// The env var clang/rustc read to decide the minimum OS version a Mach-O
+/// slice targets, keyed off the triple's platform so the artifact is
+/// actually pinned to `--ios-deployment-target` rather than whatever the
+/// build host happens to default to.
+fn deployment_target_env_var(triple: &str) -> Option<&'static str> {
+    if triple.contains("watchos") {
+        Some("WATCHOS_DEPLOYMENT_TARGET")
+    } else if triple.contains("ios") {
+        Some("IPHONEOS_DEPLOYMENT_TARGET")
+    } else {
+        None
+    }
+}
+
+/// Builds `crate_name` as a static library for `triple` via `cargo build
+/// --release --target <triple> -p <crate_name> --lib`. When `clean_env` is
+/// set, the child process gets a scrubbed environment (`PATH`,
+/// [`CARGO_TOOLCHAIN_ENV_VARS`], plus [`CRYPTO_BACKEND_ENV_VARS`]) instead of
+/// inheriting the ambient desktop shell, which otherwise routinely leaks
+/// host toolchain/env state into iOS cross-builds. `ios_deployment_target`,
+/// when set, is pinned into the child's env (clean or not) for iOS and
+/// watchOS triples so the resulting slice is reproducible regardless of what
+/// the host shell has set; macOS triples get no deployment target pinned.
+pub fn build_target_with_cargo(
+    triple: &str,
+    crate_name: &str,
+    clean_env: bool,
+    ios_deployment_target: Option<&str>,
+) -> Result<PathBuf, XcframeworkError> {
+    let mut cmd = Command::new("cargo");
+
+    if clean_env {
+        cmd.env_clear();
+        if let Ok(path) = env::var("PATH") {
+            cmd.env("PATH", path);
+        }
+        for var in CARGO_TOOLCHAIN_ENV_VARS
+            .iter()
+            .chain(CRYPTO_BACKEND_ENV_VARS)
+        {
+            if let Ok(value) = env::var(var) {
+                cmd.env(var, value);
+            }
+        }
+    }
+
+    if let (Some(target), Some(var)) = (ios_deployment_target, deployment_target_env_var(triple))
+    {
+        cmd.env(var, target);
+    }
+
+    cmd.args([
+        "build",
+        "--release",
+        "--target",
+        triple,
+        "-p",
+        crate_name,
+        "--lib",
+    ]);
+
+    let status = cmd.status()?;
+    if !status.success() {
+        return Err(XcframeworkError::Command {
+            program: "cargo",
+            status: status.to_string(),
+        });
+    }
+
+    // `-p`/`--lib` only builds what's declared in that crate's `Cargo.toml`;
+    // this assumes it declares `crate-type = ["staticlib"]`, so check the
+    // expected artifact actually landed rather than handing back a path that
+    // silently doesn't exist.
+    let artifact_name = crate_name.replace('-', "_");
+    let library = PathBuf::from("target")
+        .join(triple)
+        .join("release")
+        .join(format!("lib{artifact_name}.a"));
+
+    if !library.exists() {
+        return Err(XcframeworkError::MissingArtifact(format!(
+            "cargo build for {triple} succeeded but {} is missing - does the \
+             `{crate_name}` crate declare `crate-type = [\"staticlib\"]`?",
+            library.display()
+        )));
+    }
+
+    Ok(library)
+}
+
+/// Static lib / headers pair for a single Apple target triple, e.g.
+/// `aarch64-apple-ios`.
+pub struct TargetArtifact {
+    pub triple: String,
+    pub library: PathBuf,
+    pub headers: PathBuf,
+}
+
+pub struct XcframeworkOptions<'a> {
+    pub framework_name: &'a str,
+    pub artifacts: &'a [TargetArtifact],
+    pub module_map: &'a Path,
+    pub output_dir: &'a Path,
+    pub release_tag: &'a str,
+    pub package_swift: &'a Path,
+}
+
+#[derive(Debug)]
+pub enum XcframeworkError {
+    Io(std::io::Error),
+    Command { program: &'static str, status: String },
+    PackageSwift(String),
+    MissingArtifact(String),
+}
+
+impl fmt::Display for XcframeworkError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            XcframeworkError::Io(e) => write!(f, "io error: {e}"),
+            XcframeworkError::Command { program, status } => {
+                write!(f, "`{program}` failed: {status}")
+            }
+            XcframeworkError::PackageSwift(msg) => write!(f, "Package.swift: {msg}"),
+            XcframeworkError::MissingArtifact(msg) => write!(f, "missing build artifact: {msg}"),
+        }
+    }
+}
+
+impl std::error::Error for XcframeworkError {}
+
+impl From<std::io::Error> for XcframeworkError {
+    fn from(e: std::io::Error) -> Self {
+        XcframeworkError::Io(e)
+    }
+}
+
+/// Runs `xcodebuild -create-xcframework`, zips the output, computes its
+/// SHA-256, and rewrites the `binaryTarget(url:checksum:)` entry in
+/// `package_swift` to point at `release_tag`. Returns the path to the zip.
+pub fn build(opts: &XcframeworkOptions) -> Result<PathBuf, XcframeworkError> {
+    let xcframework_path = opts
+        .output_dir
+        .join(format!("{}.xcframework", opts.framework_name));
+
+    run_create_xcframework(opts, &xcframework_path)?;
+
+    let zip_path = opts
+        .output_dir
+        .join(format!("{}.xcframework.zip", opts.framework_name));
+    zip_xcframework(&xcframework_path, &zip_path)?;
+
+    let checksum = sha256_hex(&zip_path)?;
+    rewrite_package_swift(opts.package_swift, opts.framework_name, opts.release_tag, &checksum)?;
+
+    Ok(zip_path)
+}
+
+fn run_create_xcframework(
+    opts: &XcframeworkOptions,
+    xcframework_path: &Path,
+) -> Result<(), XcframeworkError> {
+    // A stale directory from a previous run would make `-create-xcframework`
+    // refuse to overwrite it.
+    if xcframework_path.exists() {
+        fs::remove_dir_all(xcframework_path)?;
+    }
+
+    for artifact in opts.artifacts {
+        // `xcodebuild -create-xcframework` picks up a `module.modulemap` that
+        // lives alongside the headers it's given, so stage the shared module
+        // map into each target's headers directory before invoking it.
+        fs::copy(opts.module_map, artifact.headers.join("module.modulemap"))?;
+    }
+
+    let mut cmd = Command::new("xcodebuild");
+    cmd.arg("-create-xcframework");
+
+    for artifact in opts.artifacts {
+        cmd.arg("-library")
+            .arg(&artifact.library)
+            .arg("-headers")
+            .arg(&artifact.headers);
+    }
+
+    cmd.arg("-output").arg(xcframework_path);
+
+    let status = cmd.status()?;
+    if !status.success() {
+        return Err(XcframeworkError::Command {
+            program: "xcodebuild",
+            status: status.to_string(),
+        });
+    }
+
+    Ok(())
+}
+
+/// Zips with `ditto`, the tool Apple's own Swift Package Manager docs
+/// recommend for xcframeworks, since it preserves the resource forks and
+/// symlinks that a plain `zip` can silently drop.
+fn zip_xcframework(xcframework_path: &Path, zip_path: &Path) -> Result<(), XcframeworkError> {
+    if zip_path.exists() {
+        fs::remove_file(zip_path)?;
+    }
+
+    let status = Command::new("ditto")
+        .arg("-c")
+        .arg("-k")
+        .arg("--sequesterRsrc")
+        .arg("--keepParent")
+        .arg(xcframework_path)
+        .arg(zip_path)
+        .status()?;
+
+    if !status.success() {
+        return Err(XcframeworkError::Command {
+            program: "ditto",
+            status: status.to_string(),
+        });
+    }
+
+    Ok(())
+}
+
+fn sha256_hex(path: &Path) -> Result<String, XcframeworkError> {
+    let bytes = fs::read(path)?;
+    let digest = Sha256::digest(&bytes);
+    Ok(digest.iter().map(|b| format!("{b:02x}")).collect())
+}
+
+const BINARY_TARGET_CALL: &str = ".binaryTarget(";
+
+/// Rewrites the `.binaryTarget(name: "<framework_name>", url: ..., checksum: ...)`
+/// entry in a `Package.swift` template to point at the freshly built release.
+///
+/// Only scans inside `.binaryTarget(...)` calls (not just any `name: "..."`),
+/// so a regular `.target` that happens to share the framework's name doesn't
+/// get mis-targeted.
+fn rewrite_package_swift(
+    package_swift: &Path,
+    framework_name: &str,
+    release_tag: &str,
+    checksum: &str,
+) -> Result<(), XcframeworkError> {
+    let contents = fs::read_to_string(package_swift)?;
+    let name_marker = format!("name: \"{framework_name}\"");
+
+    let mut search_from = 0;
+    let (target_start, target_end) = loop {
+        let call_offset = contents[search_from..]
+            .find(BINARY_TARGET_CALL)
+            .ok_or_else(|| {
+                XcframeworkError::PackageSwift(format!(
+                    "no .binaryTarget named `{framework_name}` found"
+                ))
+            })?;
+        let call_start = search_from + call_offset;
+        let args_start = call_start + BINARY_TARGET_CALL.len();
+        let call_end = find_matching_paren(&contents, args_start)?;
+
+        if contents[call_start..call_end].contains(&name_marker) {
+            break (call_start, call_end);
+        }
+        search_from = call_end;
+    };
+
+    let target_block = &contents[target_start..target_end];
+    let url = format!(
+        "https://github.com/tangowhisky-dev/mls-rs/releases/download/{release_tag}/{framework_name}.xcframework.zip"
+    );
+
+    let updated_block = replace_keyed_string(target_block, "url", &url)?;
+    let updated_block = replace_keyed_string(&updated_block, "checksum", checksum)?;
+
+    let mut updated = String::with_capacity(contents.len());
+    updated.push_str(&contents[..target_start]);
+    updated.push_str(&updated_block);
+    updated.push_str(&contents[target_end..]);
+
+    fs::write(package_swift, updated)?;
+    Ok(())
+}
+
+/// Finds the index of the `)` that closes the `(` implicitly opened just
+/// before `args_start`, accounting for nested parens (e.g. a `.product(...)`
+/// dependency expression inside the call's arguments).
+fn find_matching_paren(contents: &str, args_start: usize) -> Result<usize, XcframeworkError> {
+    let mut depth = 1usize;
+    for (offset, ch) in contents[args_start..].char_indices() {
+        match ch {
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Ok(args_start + offset + 1);
+                }
+            }
+            _ => {}
+        }
+    }
+    Err(XcframeworkError::PackageSwift(
+        "unterminated .binaryTarget call".to_string(),
+    ))
+}
+
+/// Replaces the quoted string value following `<key>: "` inside `block` with
+/// `value`, keeping the rest of the block untouched.
+fn replace_keyed_string(block: &str, key: &str, value: &str) -> Result<String, XcframeworkError> {
+    let needle = format!("{key}: \"");
+    let key_start = block.find(&needle).ok_or_else(|| {
+        XcframeworkError::PackageSwift(format!("missing `{key}:` entry in binaryTarget"))
+    })?;
+    let value_start = key_start + needle.len();
+    let value_end = block[value_start..]
+        .find('"')
+        .map(|offset| value_start + offset)
+        .ok_or_else(|| XcframeworkError::PackageSwift(format!("unterminated `{key}:` value")))?;
+
+    let mut updated = String::with_capacity(block.len());
+    updated.push_str(&block[..value_start]);
+    updated.push_str(value);
+    updated.push_str(&block[value_end..]);
+    Ok(updated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_temp(contents: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!(
+            "uniffi-bindgen-package-swift-test-{:?}-{}",
+            std::thread::current().id(),
+            contents.len()
+        ));
+        fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn replace_keyed_string_updates_only_the_named_key() {
+        let block = r#".binaryTarget(name: "MLS", url: "https://old", checksum: "deadbeef")"#;
+        let updated = replace_keyed_string(block, "url", "https://new").unwrap();
+        assert_eq!(
+            updated,
+            r#".binaryTarget(name: "MLS", url: "https://new", checksum: "deadbeef")"#
+        );
+    }
+
+    #[test]
+    fn rewrite_package_swift_updates_url_and_checksum() {
+        let package_swift = write_temp(
+            r#"
+let package = Package(
+    targets: [
+        .binaryTarget(
+            name: "MLS",
+            url: "https://example.com/old.zip",
+            checksum: "oldchecksum"
+        )
+    ]
+)
+"#,
+        );
+
+        rewrite_package_swift(&package_swift, "MLS", "v1.2.3", "newchecksum").unwrap();
+
+        let updated = fs::read_to_string(&package_swift).unwrap();
+        assert!(updated.contains(
+            "https://github.com/tangowhisky-dev/mls-rs/releases/download/v1.2.3/MLS.xcframework.zip"
+        ));
+        assert!(updated.contains("checksum: \"newchecksum\""));
+        assert!(!updated.contains("oldchecksum"));
+
+        fs::remove_file(&package_swift).ok();
+    }
+
+    #[test]
+    fn rewrite_package_swift_ignores_a_regular_target_with_the_same_name() {
+        // A `.target(name: "MLS", ...)` earlier in the manifest must not be
+        // mistaken for the `.binaryTarget` we're trying to update.
+        let package_swift = write_temp(
+            r#"
+let package = Package(
+    targets: [
+        .target(name: "MLS", dependencies: []),
+        .binaryTarget(
+            name: "MLS",
+            url: "https://example.com/old.zip",
+            checksum: "oldchecksum"
+        )
+    ]
+)
+"#,
+        );
+
+        rewrite_package_swift(&package_swift, "MLS", "v1.2.3", "newchecksum").unwrap();
+
+        let updated = fs::read_to_string(&package_swift).unwrap();
+        assert!(updated.contains(".target(name: \"MLS\", dependencies: [])"));
+        assert!(updated.contains("checksum: \"newchecksum\""));
+        assert!(!updated.contains("oldchecksum"));
+
+        fs::remove_file(&package_swift).ok();
+    }
+}